@@ -0,0 +1,426 @@
+//! `TObject` implementation for AVM1 arrays, with proper index holes.
+
+use crate::avm1::function::Executable;
+use crate::avm1::object::{Object, ObjectPtr, TObject};
+use crate::avm1::property::Attribute;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::script_object::ScriptObject;
+use crate::avm1::{Avm1, Error, UpdateContext, Value};
+use enumset::EnumSet;
+use gc_arena::{Collect, GcCell, MutationContext};
+
+/// The underlying data for an `ArrayObject`.
+#[derive(Collect, Debug)]
+#[collect(no_drop)]
+pub struct ArrayObjectData<'gc> {
+    /// Named (non-index) properties, the prototype, watchers, attributes
+    /// and interfaces are all relayed to a plain backing object rather
+    /// than duplicated here. Watchers registered on a numeric name also
+    /// live here (see `set_watcher`), so array index writes consult it too.
+    base: ScriptObject<'gc>,
+
+    /// Sparse array storage: `None` is a genuine hole (never defined, or
+    /// deleted via `delete_array_element`), distinct from a slot that was
+    /// explicitly set to `Value::Undefined`.
+    storage: Vec<Option<Value<'gc>>>,
+
+    /// Indices in definition order (the order they were first assigned),
+    /// so enumeration reflects "most recently defined first" like any
+    /// other property, rather than ascending numeric order. An index is
+    /// removed from here when it reverts to a hole, via
+    /// `delete_array_element` or `set_length` shrinking past it.
+    definition_order: Vec<usize>,
+}
+
+/// An AVM1 array. Numeric index storage lives here, as a dedicated sparse
+/// representation, instead of being modeled as ordinary named properties
+/// on a `ScriptObject`; everything else is relayed to an embedded
+/// `ScriptObject`.
+#[derive(Clone, Collect, Debug, Copy)]
+#[collect(no_drop)]
+pub struct ArrayObject<'gc>(GcCell<'gc, ArrayObjectData<'gc>>);
+
+impl<'gc> ArrayObject<'gc> {
+    pub fn empty(gc_context: MutationContext<'gc, '_>, proto: Option<Object<'gc>>) -> Self {
+        Self(GcCell::allocate(
+            gc_context,
+            ArrayObjectData {
+                base: ScriptObject::object(gc_context, proto),
+                storage: Vec::new(),
+                definition_order: Vec::new(),
+            },
+        ))
+    }
+
+    /// The index, if any, that `name` addresses as an array element.
+    fn index_of(name: &str) -> Option<usize> {
+        name.parse().ok()
+    }
+}
+
+impl<'gc> TObject<'gc> for ArrayObject<'gc> {
+    fn get_local(
+        &self,
+        name: &str,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        this: Object<'gc>,
+    ) -> Result<ReturnValue<'gc>, Error> {
+        if let Some(index) = Self::index_of(name) {
+            return Ok(self.get_array_element(index).into());
+        }
+
+        self.0.read().base.get_local(name, avm, context, this)
+    }
+
+    fn set(
+        &self,
+        name: &str,
+        value: Value<'gc>,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        if let Some(index) = Self::index_of(name) {
+            let old_value = self.get_array_element(index);
+            let watcher = self.0.read().base.watcher_for(name);
+            let value = if let Some(watcher) = watcher {
+                match watcher.call(avm, context, name, old_value, value, (*self).into())? {
+                    // A watcher returning `undefined` cancels the write.
+                    Value::Undefined => return Ok(()),
+                    value => value,
+                }
+            } else {
+                value
+            };
+
+            self.set_array_element(index, value, context.gc_context);
+            return Ok(());
+        }
+
+        self.0.read().base.set(name, value, avm, context)
+    }
+
+    fn call(
+        &self,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        this: Object<'gc>,
+        args: &[Value<'gc>],
+    ) -> Result<ReturnValue<'gc>, Error> {
+        self.0.read().base.call(avm, context, this, args)
+    }
+
+    fn new(
+        &self,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        this: Object<'gc>,
+        args: &[Value<'gc>],
+    ) -> Result<Object<'gc>, Error> {
+        self.0.read().base.new(avm, context, this, args)
+    }
+
+    fn delete(&self, gc_context: MutationContext<'gc, '_>, name: &str) -> bool {
+        if let Some(index) = Self::index_of(name) {
+            self.delete_array_element(index, gc_context);
+            return true;
+        }
+
+        self.0.read().base.delete(gc_context, name)
+    }
+
+    fn proto(&self) -> Option<Object<'gc>> {
+        self.0.read().base.proto()
+    }
+
+    fn define_value(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        name: &str,
+        value: Value<'gc>,
+        attributes: EnumSet<Attribute>,
+    ) {
+        if let Some(index) = Self::index_of(name) {
+            self.set_array_element(index, value, gc_context);
+            return;
+        }
+
+        self.0
+            .read()
+            .base
+            .define_value(gc_context, name, value, attributes)
+    }
+
+    fn add_property(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        name: &str,
+        get: Executable<'gc>,
+        set: Option<Executable<'gc>>,
+        attributes: EnumSet<Attribute>,
+    ) {
+        self.0
+            .read()
+            .base
+            .add_property(gc_context, name, get, set, attributes)
+    }
+
+    fn set_watcher(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        name: String,
+        callback: Object<'gc>,
+        user_data: Value<'gc>,
+    ) {
+        self.0
+            .read()
+            .base
+            .set_watcher(gc_context, name, callback, user_data)
+    }
+
+    fn remove_watcher(&self, gc_context: MutationContext<'gc, '_>, name: String) -> bool {
+        self.0.read().base.remove_watcher(gc_context, name)
+    }
+
+    fn has_property(&self, name: &str) -> bool {
+        if self.has_own_property(name) {
+            return true;
+        }
+
+        let mut proto = self.proto();
+        while let Some(proto_ob) = proto {
+            if proto_ob.has_own_property(name) {
+                return true;
+            }
+            proto = proto_ob.proto();
+        }
+
+        false
+    }
+
+    fn has_own_property(&self, name: &str) -> bool {
+        match Self::index_of(name) {
+            Some(index) => self.has_array_element(index),
+            None => self.0.read().base.has_own_property(name),
+        }
+    }
+
+    fn is_property_overwritable(&self, name: &str) -> bool {
+        if Self::index_of(name).is_some() {
+            return true;
+        }
+
+        self.0.read().base.is_property_overwritable(name)
+    }
+
+    fn is_property_enumerable(&self, name: &str) -> bool {
+        match Self::index_of(name) {
+            Some(index) => self.has_array_element(index),
+            None => self.0.read().base.is_property_enumerable(name),
+        }
+    }
+
+    fn get_keys(&self) -> Vec<String> {
+        // `for..in` visits array elements in reverse definition order, same
+        // as any other property (not ascending numeric order: `arr[5] = 1;
+        // arr[2] = 2;` enumerates "2" before "5", since 2 was set more
+        // recently), skipping holes entirely.
+        let data = self.0.read();
+        let mut keys: Vec<String> = data
+            .definition_order
+            .iter()
+            .rev()
+            .map(|index| index.to_string())
+            .collect();
+        // The backing object may hold a slot for a numeric name (created by
+        // `set_watcher`); element presence is decided by `storage` alone.
+        keys.extend(
+            data.base
+                .get_keys()
+                .into_iter()
+                .filter(|name| Self::index_of(name).is_none()),
+        );
+        keys
+    }
+
+    fn own_property_names(&self) -> Vec<String> {
+        let data = self.0.read();
+        let mut keys: Vec<String> = data
+            .definition_order
+            .iter()
+            .map(|index| index.to_string())
+            .collect();
+        keys.extend(data.base.own_property_names());
+        keys
+    }
+
+    fn as_string(&self) -> String {
+        self.0
+            .read()
+            .storage
+            .iter()
+            .map(|slot| match slot {
+                Some(value) => value.clone().coerce_to_string().unwrap_or_default(),
+                None => String::new(),
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    fn type_of(&self) -> &'static str {
+        "object"
+    }
+
+    fn as_script_object(&self) -> Option<ScriptObject<'gc>> {
+        Some(self.0.read().base)
+    }
+
+    fn as_display_object(&self) -> Option<crate::display_object::DisplayObject<'gc>> {
+        None
+    }
+
+    fn as_executable(&self) -> Option<Executable<'gc>> {
+        None
+    }
+
+    fn as_ptr(&self) -> *const ObjectPtr {
+        self.0.as_ptr() as *const ObjectPtr
+    }
+
+    fn set_attributes(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        name: Option<&str>,
+        set_flags: EnumSet<Attribute>,
+        clear_flags: EnumSet<Attribute>,
+    ) {
+        // Array elements don't carry their own attributes; only the named
+        // properties relayed to the backing object do.
+        self.0
+            .read()
+            .base
+            .set_attributes(gc_context, name, set_flags, clear_flags)
+    }
+
+    fn add_interface(&self, gc_context: MutationContext<'gc, '_>, constructor: Object<'gc>) {
+        self.0.read().base.add_interface(gc_context, constructor)
+    }
+
+    fn interfaces(&self) -> Vec<Object<'gc>> {
+        self.0.read().base.interfaces()
+    }
+
+    fn get_length(&self) -> usize {
+        self.0.read().storage.len()
+    }
+
+    fn get_array(&self) -> Vec<Option<Value<'gc>>> {
+        self.0.read().storage.clone()
+    }
+
+    fn set_length(&self, gc_context: MutationContext<'gc, '_>, length: usize) {
+        let mut data = self.0.write(gc_context);
+        data.storage.resize(length, None);
+        data.definition_order.retain(|&index| index < length);
+    }
+
+    fn has_array_element(&self, index: usize) -> bool {
+        matches!(self.0.read().storage.get(index), Some(Some(_)))
+    }
+
+    fn get_array_element(&self, index: usize) -> Value<'gc> {
+        self.0
+            .read()
+            .storage
+            .get(index)
+            .copied()
+            .flatten()
+            .unwrap_or(Value::Undefined)
+    }
+
+    fn set_array_element(
+        &self,
+        index: usize,
+        value: Value<'gc>,
+        gc_context: MutationContext<'gc, '_>,
+    ) -> usize {
+        let mut data = self.0.write(gc_context);
+        if index >= data.storage.len() {
+            data.storage.resize(index + 1, None);
+        }
+        if data.storage[index].is_none() {
+            data.definition_order.push(index);
+        }
+        data.storage[index] = Some(value);
+        data.storage.len()
+    }
+
+    fn delete_array_element(&self, index: usize, gc_context: MutationContext<'gc, '_>) {
+        let mut data = self.0.write(gc_context);
+        if let Some(slot) = data.storage.get_mut(index) {
+            if slot.take().is_some() {
+                data.definition_order.retain(|&i| i != index);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::script_object::ScriptObject;
+    use crate::avm1::test_utils::with_avm;
+
+    #[test]
+    fn get_keys_is_reverse_definition_order_not_index_order() {
+        with_avm(19, |_avm, context, _root| {
+            let array = ArrayObject::empty(context.gc_context, None);
+            array.set_array_element(5, Value::Number(1.0), context.gc_context);
+            array.set_array_element(2, Value::Number(2.0), context.gc_context);
+
+            assert_eq!(array.get_keys(), vec!["2".to_string(), "5".to_string()]);
+        });
+    }
+
+    #[test]
+    fn get_array_preserves_index_alignment_across_holes() {
+        with_avm(19, |_avm, context, _root| {
+            let array = ArrayObject::empty(context.gc_context, None);
+            array.set_array_element(0, Value::Number(1.0), context.gc_context);
+            array.set_array_element(2, Value::Number(3.0), context.gc_context);
+
+            let values = array.get_array();
+            assert_eq!(values, vec![
+                Some(Value::Number(1.0)),
+                None,
+                Some(Value::Number(3.0)),
+            ]);
+        });
+    }
+
+    #[test]
+    fn set_on_index_invokes_a_watcher_registered_through_base() {
+        with_avm(19, |avm, context, _root| {
+            let array = ArrayObject::empty(context.gc_context, None);
+            array.set_array_element(0, Value::Number(1.0), context.gc_context);
+
+            let callback = ScriptObject::function(
+                context.gc_context,
+                Executable::Native(|_avm, _context, _this, _args| Ok(Value::Number(42.0).into())),
+                None,
+            );
+            array.set_watcher(
+                context.gc_context,
+                "0".to_string(),
+                callback.into(),
+                Value::Undefined,
+            );
+
+            array
+                .set("0", Value::Number(2.0), avm, context)
+                .unwrap();
+
+            assert_eq!(array.get_array_element(0), Value::Number(42.0));
+        });
+    }
+}
@@ -0,0 +1,193 @@
+//! `Object` class built-ins: `Object.prototype.watch`/`unwatch` and the
+//! legacy `ASSetPropFlags` global function.
+
+use crate::avm1::property::Attribute;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Error, Object, TObject, UpdateContext, Value};
+use enumset::EnumSet;
+
+/// `Object.prototype.watch(name, callback, [userData])`
+///
+/// Registers `callback` to be invoked whenever `this[name]` is set via
+/// ordinary scripted assignment; see `TObject::set_watcher`.
+pub fn watch<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let name = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string()?;
+    let callback = match args.get(1) {
+        Some(Value::Object(callback)) => *callback,
+        _ => return Ok(Value::Bool(false).into()),
+    };
+    let user_data = args.get(2).cloned().unwrap_or(Value::Undefined);
+
+    this.set_watcher(context.gc_context, name, callback, user_data);
+
+    Ok(Value::Bool(true).into())
+}
+
+/// `Object.prototype.unwatch(name)`
+pub fn unwatch<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let name = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string()?;
+
+    Ok(Value::Bool(this.remove_watcher(context.gc_context, name)).into())
+}
+
+/// `ASSetPropFlags(obj, props, setFlags, [clearFlags])`
+///
+/// `props` may be `null`/`undefined` (every own property of `obj`), an
+/// array of property names, or a comma- or space-separated string of
+/// names. Each named property has `setFlags` OR'd in and `clearFlags`
+/// removed via `TObject::set_attributes`.
+pub fn as_set_prop_flags<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let object = match args.get(0) {
+        Some(Value::Object(object)) => *object,
+        _ => return Ok(Value::Undefined.into()),
+    };
+
+    let set_flags = attributes_from_bits(
+        args.get(2)
+            .cloned()
+            .unwrap_or(Value::Number(0.0))
+            .coerce_to_f64()? as u32,
+    );
+    let clear_flags = attributes_from_bits(
+        args.get(3)
+            .cloned()
+            .unwrap_or(Value::Number(0.0))
+            .coerce_to_f64()? as u32,
+    );
+
+    match args.get(1) {
+        None | Some(Value::Undefined) | Some(Value::Null) => {
+            object.set_attributes(context.gc_context, None, set_flags, clear_flags);
+        }
+        Some(Value::Object(props)) if props.as_display_object().is_none() => {
+            // An array of property names; holes are skipped rather than
+            // coerced to "undefined". A non-array object has no array
+            // storage, so it names no properties.
+            for value in props.get_array().into_iter().flatten() {
+                let name = value.coerce_to_string()?;
+                object.set_attributes(context.gc_context, Some(&name), set_flags, clear_flags);
+            }
+        }
+        Some(value) => {
+            let props = value.coerce_to_string()?;
+            for name in props
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|name| !name.is_empty())
+            {
+                object.set_attributes(context.gc_context, Some(name), set_flags, clear_flags);
+            }
+        }
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+/// Decodes an `ASSetPropFlags` bitmask (bit 0 = `DontEnum`, bit 1 =
+/// `DontDelete`, bit 2 = `ReadOnly`) into the corresponding attribute set.
+fn attributes_from_bits(bits: u32) -> EnumSet<Attribute> {
+    let mut attributes = EnumSet::new();
+    if bits & 0b001 != 0 {
+        attributes |= Attribute::DontEnum;
+    }
+    if bits & 0b010 != 0 {
+        attributes |= Attribute::DontDelete;
+    }
+    if bits & 0b100 != 0 {
+        attributes |= Attribute::ReadOnly;
+    }
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::script_object::ScriptObject;
+    use crate::avm1::test_utils::with_avm;
+
+    #[test]
+    fn as_set_prop_flags_makes_a_named_property_read_only() {
+        with_avm(19, |avm, context, _root| {
+            let object = ScriptObject::bare_object(context.gc_context);
+            object.define_value(
+                context.gc_context,
+                "x",
+                Value::Number(1.0),
+                Default::default(),
+            );
+
+            as_set_prop_flags(
+                avm,
+                context,
+                object.into(),
+                &[
+                    object.into(),
+                    Value::String("x".to_string()),
+                    Value::Number(4.0), // ReadOnly
+                ],
+            )
+            .unwrap();
+
+            object
+                .set("x", Value::Number(2.0), avm, context)
+                .unwrap();
+            let value = object
+                .get_local("x", avm, context, object.into())
+                .unwrap()
+                .resolve(avm, context)
+                .unwrap();
+            assert_eq!(value, Value::Number(1.0));
+        });
+    }
+
+    #[test]
+    fn as_set_prop_flags_with_no_props_argument_touches_every_own_property() {
+        with_avm(19, |avm, context, _root| {
+            let object = ScriptObject::bare_object(context.gc_context);
+            object.define_value(
+                context.gc_context,
+                "x",
+                Value::Number(1.0),
+                Default::default(),
+            );
+            object.define_value(
+                context.gc_context,
+                "y",
+                Value::Number(2.0),
+                Default::default(),
+            );
+
+            as_set_prop_flags(
+                avm,
+                context,
+                object.into(),
+                &[object.into(), Value::Null, Value::Number(1.0)], // DontEnum
+            )
+            .unwrap();
+
+            assert!(object.get_keys().is_empty());
+        });
+    }
+}
@@ -3,11 +3,12 @@
 use crate::avm1::function::Executable;
 use crate::avm1::property::Attribute;
 use crate::avm1::return_value::ReturnValue;
-use crate::avm1::{Avm1, Error, ScriptObject, StageObject, UpdateContext, Value};
+use crate::avm1::{ArrayObject, Avm1, Error, ScriptObject, StageObject, UpdateContext, Value};
 use crate::display_object::DisplayObject;
 use enumset::EnumSet;
 use gc_arena::{Collect, MutationContext};
 use ruffle_macros::enum_trait_object;
+use std::cell::RefCell;
 use std::collections::HashSet;
 use std::fmt::Debug;
 
@@ -19,6 +20,7 @@ use std::fmt::Debug;
     pub enum Object<'gc> {
         ScriptObject(ScriptObject<'gc>),
         StageObject(StageObject<'gc>),
+        ArrayObject(ArrayObject<'gc>),
     }
 )]
 pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy {
@@ -39,6 +41,12 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     ) -> Result<ReturnValue<'gc>, Error>;
 
     /// Retrieve a named property from the object, or it's prototype.
+    ///
+    /// If the property is not found anywhere in the prototype chain, and
+    /// the object (or one of its prototypes) defines a callable `__resolve`
+    /// property, that function is invoked with `this` bound to `self` and
+    /// the missing property name as its sole argument, and its result is
+    /// returned instead of `undefined`.
     fn get(
         &self,
         name: &str,
@@ -47,12 +55,21 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     ) -> Result<ReturnValue<'gc>, Error> {
         if self.has_own_property(name) {
             self.get_local(name, avm, context, (*self).into())
-        } else {
+        } else if self.has_property(name) {
             search_prototype(self.proto(), name, avm, context, (*self).into())
+        } else {
+            resolve_undefined_trap(*self, name, avm, context)
         }
     }
 
     /// Set a named property on this object, or it's prototype.
+    ///
+    /// Implementations must consult any watcher registered via
+    /// `set_watcher` before the value is actually written: the watcher's
+    /// callback is invoked as `callback(name, oldValue, newValue, userData)`
+    /// with `this` bound to the object, and its return value replaces
+    /// `value` (returning `undefined` cancels the write). Watchers only
+    /// fire for this scripted path, never for `define_value`.
     fn set(
         &self,
         name: &str,
@@ -143,11 +160,37 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         attributes: EnumSet<Attribute>,
     );
 
+    /// Set a watchpoint on a named property.
+    ///
+    /// Whenever `set` is about to change `name`, `callback` is `call`ed as
+    /// `callback(name, oldValue, newValue, userData)` with `this` bound to
+    /// this object, and the value it returns is written in place of
+    /// `newValue`. This mirrors `Object.watch` in Flash. A watcher set on
+    /// this object intercepts sets to properties of the same name even if
+    /// those properties only exist on the prototype chain.
+    fn set_watcher(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        name: String,
+        callback: Object<'gc>,
+        user_data: Value<'gc>,
+    );
+
+    /// Removes a watchpoint on a named property.
+    ///
+    /// Returns `true` if a watcher was present and removed, mirroring
+    /// `Object.unwatch`.
+    fn remove_watcher(&self, gc_context: MutationContext<'gc, '_>, name: String) -> bool;
+
     /// Checks if the object has a given named property.
     fn has_property(&self, name: &str) -> bool;
 
     /// Checks if the object has a given named property on itself (and not,
-    /// say, the object's prototype or superclass)
+    /// say, the object's prototype or superclass).
+    ///
+    /// For array-backed objects, a numeric name is only considered present
+    /// if the corresponding index is a present element rather than a hole;
+    /// see `has_array_element`.
     fn has_own_property(&self, name: &str) -> bool;
 
     /// Checks if a named property can be overwritten.
@@ -156,8 +199,66 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     /// Checks if a named property appears when enumerating the object.
     fn is_property_enumerable(&self, name: &str) -> bool;
 
-    /// Enumerate the object.
-    fn get_keys(&self) -> HashSet<String>;
+    /// Bulk-edit the attributes of existing own properties, mirroring the
+    /// `ASSetPropFlags` builtin.
+    ///
+    /// `set_flags` are OR'd onto each targeted property's attributes and
+    /// `clear_flags` are removed; if a bit is present in both, clearing
+    /// takes precedence. When `name` is `Some`, only that property is
+    /// touched; when `name` is `None`, every own property is touched. This
+    /// never creates a property: it silently no-ops on a name that doesn't
+    /// already exist.
+    fn set_attributes(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        name: Option<&str>,
+        set_flags: EnumSet<Attribute>,
+        clear_flags: EnumSet<Attribute>,
+    );
+
+    /// Enumerate the object's own, enumerable properties.
+    ///
+    /// Keys are returned in reverse definition order (the order in which
+    /// AVM1's `for..in` visits an object's own properties), and exclude any
+    /// property for which `is_property_enumerable` is `false`. This does not
+    /// walk the prototype chain; use `all_keys` for that.
+    fn get_keys(&self) -> Vec<String>;
+
+    /// All of the object's own property names, including non-enumerable
+    /// ones, in no particular order.
+    ///
+    /// This exists for shadow detection in `all_keys`: a non-enumerable
+    /// property still hides an identically-named, enumerable property
+    /// further up the prototype chain from `for..in`, even though it
+    /// doesn't appear in enumeration itself, so `all_keys` needs to know
+    /// about it without it being reported by `get_keys`.
+    fn own_property_names(&self) -> Vec<String>;
+
+    /// Enumerate this object's own enumerable properties together with
+    /// those of its entire prototype chain, in `for..in` order: own keys
+    /// first (reverse definition order), followed by each prototype's own
+    /// keys in turn, with any name already defined further down the chain
+    /// skipped so a child property correctly hides an identically-named
+    /// parent property, whether or not the child's property is itself
+    /// enumerable.
+    fn all_keys(&self) -> Vec<String> {
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut keys = Vec::new();
+
+        let mut proto = Some((*self).into());
+        while let Some(this) = proto {
+            for key in this.get_keys() {
+                if !seen.contains(&key) {
+                    keys.push(key.clone());
+                }
+            }
+
+            seen.extend(this.own_property_names());
+            proto = this.proto();
+        }
+
+        keys
+    }
 
     /// Coerce the object into a string.
     fn as_string(&self) -> String;
@@ -191,28 +292,78 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         false
     }
 
+    /// Register a constructor as an interface implemented by this object,
+    /// as if declared with AS2's `implements` keyword.
+    fn add_interface(&self, gc_context: MutationContext<'gc, '_>, constructor: Object<'gc>);
+
+    /// List the interface constructors this object declares itself to
+    /// implement (not including those of its prototype chain).
+    fn interfaces(&self) -> Vec<Object<'gc>>;
+
+    /// Check if this object implements the interface represented by
+    /// `constructor`, either directly or via any object in its prototype
+    /// chain, so that `instanceof` against an AS2 `interface` works
+    /// alongside the ordinary `is_prototype_of` check.
+    fn implements_interface(&self, constructor: Object<'gc>) -> bool {
+        if self
+            .interfaces()
+            .iter()
+            .any(|interface| Object::ptr_eq(*interface, constructor))
+        {
+            return true;
+        }
+
+        let mut proto = self.proto();
+        while let Some(proto_ob) = proto {
+            if proto_ob
+                .interfaces()
+                .iter()
+                .any(|interface| Object::ptr_eq(*interface, constructor))
+            {
+                return true;
+            }
+
+            proto = proto_ob.proto();
+        }
+
+        false
+    }
+
     /// Get the length of this object, as if it were an array.
     fn get_length(&self) -> usize;
 
     /// Gets a copy of the array storage behind this object.
-    fn get_array(&self) -> Vec<Value<'gc>>;
+    ///
+    /// The returned `Vec` is index-aligned with this object's array
+    /// indices (`result[i]` corresponds to index `i`): a hole (never
+    /// defined, or deleted via `delete_array_element`) is `None`, which
+    /// callers can tell apart from a slot explicitly set to
+    /// `Value::Undefined` (`Some(Value::Undefined)`).
+    fn get_array(&self) -> Vec<Option<Value<'gc>>>;
 
     /// Sets the length of this object, as if it were an array.
     ///
-    /// Increasing this value will fill the gap with Value::Undefined.
+    /// Increasing this value creates holes, not `Value::Undefined` entries.
     /// Decreasing this value will remove affected items from both the array and properties storage.
     fn set_length(&self, gc_context: MutationContext<'gc, '_>, length: usize);
 
+    /// Checks if a given array index is present (as opposed to a hole).
+    ///
+    /// This does not respect the prototype chain.
+    fn has_array_element(&self, index: usize) -> bool;
+
     /// Gets a property of this object as if it were an array.
     ///
     /// Array element lookups do not respect the prototype chain, and will ignore virtual properties.
+    /// A hole reads back as `Value::Undefined`; use `has_array_element` to tell a hole apart from a
+    /// slot that was explicitly set to `undefined`.
     fn get_array_element(&self, index: usize) -> Value<'gc>;
 
     /// Sets a property of this object as if it were an array.
     ///
     /// This will increase the "length" of this object to encompass the index, and return the new length.
-    /// Any gap created by increasing the length will be filled with Value::Undefined, both in array
-    /// and property storage.
+    /// Any gap created by increasing the length is left as holes, both in array and property storage;
+    /// only `index` itself becomes a present element.
     fn set_array_element(
         &self,
         index: usize,
@@ -222,8 +373,9 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
 
     /// Deletes a property of this object as if it were an array.
     ///
-    /// This will not rearrange the array or adjust the length, nor will it affect the properties
-    /// storage.
+    /// This produces a genuine hole at `index`, skipped by `get_keys`/enumeration and reported as
+    /// absent by `has_array_element`, rather than leaving behind a `Value::Undefined` element. It
+    /// will not rearrange the array or adjust the length, nor will it affect the properties storage.
     fn delete_array_element(&self, index: usize, gc_context: MutationContext<'gc, '_>);
 }
 
@@ -259,3 +411,219 @@ pub fn search_prototype<'gc>(
 
     Ok(Value::Undefined.into())
 }
+
+thread_local! {
+    /// `(object, name)` pairs currently being resolved via `__resolve`,
+    /// used to guard against a resolver that misses on the very name it's
+    /// resolving. Keyed by object pointer as well as name so that an
+    /// unrelated object's lookup of the same name (e.g. as a side effect
+    /// triggered from within a resolver) is not incorrectly suppressed.
+    static RESOLVING: RefCell<Vec<(*const ObjectPtr, String)>> = RefCell::new(Vec::new());
+}
+
+/// Fall back to a `__resolve` handler when `name` is missing from `this` and
+/// its entire prototype chain.
+///
+/// If `this` (or a prototype) defines a callable `__resolve`, it is invoked
+/// as `__resolve(name)` with `this` bound to the original object. A
+/// `(this, name)` pair that is already being resolved is skipped (returning
+/// `undefined`) so that a `__resolve` implementation which itself misses on
+/// `name` on the same object cannot recurse forever.
+fn resolve_undefined_trap<'gc>(
+    this: Object<'gc>,
+    name: &str,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<ReturnValue<'gc>, Error> {
+    let key = (this.as_ptr(), name.to_string());
+    let already_resolving = RESOLVING.with(|r| r.borrow().contains(&key));
+    if already_resolving || !this.has_property("__resolve") {
+        return Ok(Value::Undefined.into());
+    }
+
+    RESOLVING.with(|r| r.borrow_mut().push(key.clone()));
+    let result = (|| {
+        let resolver = this.get("__resolve", avm, context)?.resolve(avm, context)?;
+
+        match resolver {
+            Value::Object(resolver) if resolver.as_executable().is_some() => resolver
+                .call(avm, context, this, &[Value::String(name.to_string())])
+                .map(Into::into),
+            _ => Ok(Value::Undefined.into()),
+        }
+    })();
+    RESOLVING.with(|r| {
+        r.borrow_mut().pop();
+    });
+
+    result
+}
+
+/// The full AVM1 `instanceof` check: true if `constructor`'s `prototype` is
+/// in `obj`'s prototype chain, or `obj` (or something in its prototype
+/// chain) declares `constructor` as an implemented interface via AS2's
+/// `implements` keyword.
+///
+/// The `instanceof` opcode handler should call this instead of calling
+/// `is_prototype_of` on its own, so that interfaces registered with
+/// `add_interface` are honored alongside ordinary prototype-chain checks.
+pub fn instance_of<'gc>(
+    obj: Object<'gc>,
+    constructor: Object<'gc>,
+    constructor_proto: Object<'gc>,
+) -> bool {
+    constructor_proto.is_prototype_of(obj) || obj.implements_interface(constructor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::function::Executable;
+    use crate::avm1::script_object::ScriptObject;
+    use crate::avm1::test_utils::with_avm;
+
+    #[test]
+    fn get_falls_back_to_resolve_when_property_is_entirely_missing() {
+        with_avm(19, |avm, context, _root| {
+            let object = ScriptObject::bare_object(context.gc_context);
+            let resolver = ScriptObject::function(
+                context.gc_context,
+                Executable::Native(|_avm, _context, _this, args| {
+                    Ok(Value::String(format!("resolved:{}", args[0].clone().coerce_to_string()?)).into())
+                }),
+                None,
+            );
+            object.define_value(
+                context.gc_context,
+                "__resolve",
+                resolver.into(),
+                Attribute::DontEnum.into(),
+            );
+
+            let value = object
+                .get("missing", avm, context)
+                .unwrap()
+                .resolve(avm, context)
+                .unwrap();
+            assert_eq!(value, Value::String("resolved:missing".to_string()));
+        });
+    }
+
+    #[test]
+    fn resolve_recursion_guard_does_not_suppress_a_different_objects_lookup() {
+        with_avm(19, |avm, context, _root| {
+            // A resolver that itself misses on the same name shouldn't
+            // recurse forever, but an unrelated object resolving the same
+            // name while that resolver is still running should still get a
+            // real answer.
+            let resolver = ScriptObject::function(
+                context.gc_context,
+                Executable::Native(|avm, context, this, args| {
+                    let name = args[0].clone().coerce_to_string()?;
+                    let again = this.get(&name, avm, context)?.resolve(avm, context)?;
+                    assert_eq!(again, Value::Undefined);
+
+                    match this.get("other", avm, context)?.resolve(avm, context)? {
+                        Value::Object(other) => other.get(&name, avm, context),
+                        _ => Ok(Value::Undefined.into()),
+                    }
+                }),
+                None,
+            );
+            let other_resolver = ScriptObject::function(
+                context.gc_context,
+                Executable::Native(|_avm, _context, _this, args| {
+                    Ok(Value::String(format!("other:{}", args[0].clone().coerce_to_string()?)).into())
+                }),
+                None,
+            );
+
+            let other = ScriptObject::bare_object(context.gc_context);
+            other.define_value(
+                context.gc_context,
+                "__resolve",
+                other_resolver.into(),
+                Attribute::DontEnum.into(),
+            );
+
+            let recursive = ScriptObject::bare_object(context.gc_context);
+            recursive.define_value(
+                context.gc_context,
+                "__resolve",
+                resolver.into(),
+                Attribute::DontEnum.into(),
+            );
+            recursive.define_value(
+                context.gc_context,
+                "other",
+                other.into(),
+                Default::default(),
+            );
+
+            let value = recursive
+                .get("missing", avm, context)
+                .unwrap()
+                .resolve(avm, context)
+                .unwrap();
+            assert_eq!(value, Value::String("other:missing".to_string()));
+        });
+    }
+
+    #[test]
+    fn instance_of_consults_both_prototype_chain_and_interfaces() {
+        with_avm(19, |_avm, context, _root| {
+            let constructor_proto = ScriptObject::bare_object(context.gc_context);
+            let interface = ScriptObject::bare_object(context.gc_context);
+
+            let prototype_instance =
+                ScriptObject::object(context.gc_context, Some(constructor_proto.into()));
+            let interface_instance = ScriptObject::bare_object(context.gc_context);
+            interface_instance.add_interface(context.gc_context, interface.into());
+
+            let unrelated = ScriptObject::bare_object(context.gc_context);
+
+            assert!(instance_of(
+                prototype_instance.into(),
+                constructor_proto.into(),
+                constructor_proto.into(),
+            ));
+            assert!(instance_of(
+                interface_instance.into(),
+                interface.into(),
+                constructor_proto.into(),
+            ));
+            assert!(!instance_of(
+                unrelated.into(),
+                interface.into(),
+                constructor_proto.into(),
+            ));
+        });
+    }
+
+    #[test]
+    fn all_keys_lets_a_non_enumerable_own_property_shadow_an_enumerable_one() {
+        with_avm(19, |_avm, context, _root| {
+            let parent = ScriptObject::bare_object(context.gc_context);
+            parent.define_value(
+                context.gc_context,
+                "x",
+                Value::Number(1.0),
+                Default::default(),
+            );
+
+            let child = ScriptObject::object(context.gc_context, Some(parent.into()));
+            child.define_value(
+                context.gc_context,
+                "x",
+                Value::Number(2.0),
+                Attribute::DontEnum.into(),
+            );
+
+            // `x` is hidden from `for..in` on `child` itself...
+            assert!(!child.get_keys().contains(&"x".to_string()));
+            // ...but still shadows the parent's enumerable `x`, so it must
+            // not reappear via the prototype chain either.
+            assert!(!Object::from(child).all_keys().contains(&"x".to_string()));
+        });
+    }
+}
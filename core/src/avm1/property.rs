@@ -0,0 +1,187 @@
+//! User-defined properties
+
+use crate::avm1::function::Executable;
+use crate::avm1::object::Object;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Error, UpdateContext, Value};
+use enumset::{EnumSet, EnumSetType};
+use gc_arena::Collect;
+
+/// Attribute flags that can be toggled on a property, mirroring the bits
+/// accepted by AVM1's `ASSetPropFlags` builtin.
+#[derive(EnumSetType, Debug)]
+pub enum Attribute {
+    DontEnum,
+    DontDelete,
+    ReadOnly,
+}
+
+/// A callback registered on a particular property name via `Object.watch`.
+#[derive(Clone, Collect, Debug, Copy)]
+#[collect(no_drop)]
+pub struct Watcher<'gc> {
+    callback: Object<'gc>,
+    user_data: Value<'gc>,
+}
+
+impl<'gc> Watcher<'gc> {
+    pub fn new(callback: Object<'gc>, user_data: Value<'gc>) -> Self {
+        Self {
+            callback,
+            user_data,
+        }
+    }
+
+    /// Invoke this watcher as `callback(name, oldValue, newValue, userData)`
+    /// with `this` bound to the watched object, returning the value that
+    /// should actually be stored in place of `new_value`.
+    pub fn call(
+        &self,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        name: &str,
+        old_value: Value<'gc>,
+        new_value: Value<'gc>,
+        this: Object<'gc>,
+    ) -> Result<Value<'gc>, Error> {
+        let args = [
+            Value::String(name.to_string()),
+            old_value,
+            new_value,
+            self.user_data,
+        ];
+
+        self.callback
+            .call(avm, context, this, &args)?
+            .resolve(avm, context)
+    }
+}
+
+/// A single named property slot: either a plain stored value or a virtual
+/// (getter/setter) property, together with its attributes and an optional
+/// watchpoint installed via `Object.watch`.
+#[derive(Clone, Collect, Debug, Copy)]
+#[collect(no_drop)]
+pub enum Property<'gc> {
+    Stored {
+        value: Value<'gc>,
+        attributes: EnumSet<Attribute>,
+        watcher: Option<Watcher<'gc>>,
+    },
+    Virtual {
+        get: Executable<'gc>,
+        set: Option<Executable<'gc>>,
+        attributes: EnumSet<Attribute>,
+        watcher: Option<Watcher<'gc>>,
+    },
+}
+
+impl<'gc> Property<'gc> {
+    pub fn attributes(&self) -> EnumSet<Attribute> {
+        match self {
+            Property::Stored { attributes, .. } => *attributes,
+            Property::Virtual { attributes, .. } => *attributes,
+        }
+    }
+
+    /// Applies an `ASSetPropFlags`-style edit: `set_flags` are OR'd in,
+    /// `clear_flags` are removed, with clearing taking precedence when a
+    /// bit appears in both.
+    pub fn set_attributes(
+        &mut self,
+        set_flags: EnumSet<Attribute>,
+        clear_flags: EnumSet<Attribute>,
+    ) {
+        let attributes = match self {
+            Property::Stored { attributes, .. } => attributes,
+            Property::Virtual { attributes, .. } => attributes,
+        };
+        *attributes = (*attributes | set_flags) - clear_flags;
+    }
+
+    pub fn watcher(&self) -> Option<Watcher<'gc>> {
+        match self {
+            Property::Stored { watcher, .. } => *watcher,
+            Property::Virtual { watcher, .. } => *watcher,
+        }
+    }
+
+    pub fn set_watcher(&mut self, watcher: Option<Watcher<'gc>>) {
+        match self {
+            Property::Stored { watcher: w, .. } => *w = watcher,
+            Property::Virtual { watcher: w, .. } => *w = watcher,
+        }
+    }
+}
+
+/// An ordered, name-keyed collection of `Property` slots.
+///
+/// Definition order is preserved (as a simple insertion-ordered association
+/// list — AVM1 objects rarely hold more than a handful of own properties,
+/// so a linear scan is cheaper here than a second index structure) so that
+/// `for..in` enumeration can visit an object's own properties in reverse
+/// definition order, matching Flash.
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub struct PropertyMap<'gc> {
+    entries: Vec<(String, Property<'gc>)>,
+}
+
+impl<'gc> PropertyMap<'gc> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Property<'gc>> {
+        self.entries.iter().find(|(k, _)| k == name).map(|(_, v)| v)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Property<'gc>> {
+        self.entries
+            .iter_mut()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v)
+    }
+
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.entries.iter().any(|(k, _)| k == name)
+    }
+
+    /// Inserts or replaces a property. A name that already exists keeps its
+    /// existing position in definition order, matching how redefining a
+    /// property in Flash does not move it to the end of `for..in`.
+    pub fn insert(&mut self, name: &str, prop: Property<'gc>) {
+        if let Some(existing) = self.get_mut(name) {
+            *existing = prop;
+        } else {
+            self.entries.push((name.to_string(), prop));
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Property<'gc>> {
+        let index = self.entries.iter().position(|(k, _)| k == name)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Own property names in reverse definition order, matching the order
+    /// `for..in` visits an object's own properties.
+    pub fn keys_reverse_insertion_order(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().rev().map(|(k, _)| k.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Property<'gc>)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&str, &mut Property<'gc>)> {
+        self.entries.iter_mut().map(|(k, v)| (k.as_str(), v))
+    }
+}
+
+impl<'gc> Default for PropertyMap<'gc> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
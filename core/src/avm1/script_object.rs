@@ -0,0 +1,506 @@
+//! The default `TObject` implementation backing plain AVM1 objects.
+
+use crate::avm1::function::Executable;
+use crate::avm1::object::{Object, ObjectPtr, TObject};
+use crate::avm1::property::{Attribute, Property, PropertyMap, Watcher};
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Error, UpdateContext, Value};
+use enumset::EnumSet;
+use gc_arena::{Collect, GcCell, MutationContext};
+
+/// The underlying data for a `ScriptObject`.
+#[derive(Collect, Debug)]
+#[collect(no_drop)]
+pub struct ScriptObjectData<'gc> {
+    proto: Option<Object<'gc>>,
+    properties: PropertyMap<'gc>,
+    interfaces: Vec<Object<'gc>>,
+    type_of: &'static str,
+    /// Present on function objects (e.g. the native builtins installed on
+    /// `Object.prototype`); `call` invokes this when set.
+    function: Option<Executable<'gc>>,
+}
+
+/// A plain, general-purpose AVM1 object: the default backing for object
+/// literals, `Function`/class instances, and anything else that isn't a
+/// display object or an array.
+#[derive(Clone, Collect, Debug, Copy)]
+#[collect(no_drop)]
+pub struct ScriptObject<'gc>(GcCell<'gc, ScriptObjectData<'gc>>);
+
+impl<'gc> ScriptObject<'gc> {
+    pub fn object(gc_context: MutationContext<'gc, '_>, proto: Option<Object<'gc>>) -> Self {
+        Self(GcCell::allocate(
+            gc_context,
+            ScriptObjectData {
+                proto,
+                properties: PropertyMap::new(),
+                interfaces: Vec::new(),
+                type_of: "object",
+                function: None,
+            },
+        ))
+    }
+
+    pub fn bare_object(gc_context: MutationContext<'gc, '_>) -> Self {
+        Self::object(gc_context, None)
+    }
+
+    /// Builds a callable object backed by `function`, e.g. for installing a
+    /// native builtin as a property value.
+    pub fn function(
+        gc_context: MutationContext<'gc, '_>,
+        function: Executable<'gc>,
+        proto: Option<Object<'gc>>,
+    ) -> Self {
+        Self(GcCell::allocate(
+            gc_context,
+            ScriptObjectData {
+                proto,
+                properties: PropertyMap::new(),
+                interfaces: Vec::new(),
+                type_of: "function",
+                function: Some(function),
+            },
+        ))
+    }
+
+    /// Looks up a registered watcher by name without going through
+    /// `get`/`set`.
+    ///
+    /// `ArrayObject` relays watcher storage for numeric names to a backing
+    /// `ScriptObject` (see `TObject::set_watcher`) but keeps array element
+    /// storage itself separate, so it uses this to consult a watcher
+    /// before writing to its own sparse storage.
+    pub(crate) fn watcher_for(&self, name: &str) -> Option<Watcher<'gc>> {
+        self.0.read().properties.get(name).and_then(Property::watcher)
+    }
+}
+
+impl<'gc> TObject<'gc> for ScriptObject<'gc> {
+    fn get_local(
+        &self,
+        name: &str,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        this: Object<'gc>,
+    ) -> Result<ReturnValue<'gc>, Error> {
+        match self.0.read().properties.get(name) {
+            Some(Property::Virtual { get, .. }) => get.exec(avm, context, this, &[]),
+            Some(Property::Stored { value, .. }) => Ok((*value).into()),
+            None => Ok(Value::Undefined.into()),
+        }
+    }
+
+    fn set(
+        &self,
+        name: &str,
+        value: Value<'gc>,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        let old_value = self.get_local(name, avm, context, (*self).into())?;
+        let old_value = old_value.resolve(avm, context)?;
+        let watcher = self
+            .0
+            .read()
+            .properties
+            .get(name)
+            .and_then(Property::watcher);
+
+        let value = if let Some(watcher) = watcher {
+            match watcher.call(avm, context, name, old_value, value, (*self).into())? {
+                // A watcher returning `undefined` cancels the write.
+                Value::Undefined => return Ok(()),
+                value => value,
+            }
+        } else {
+            value
+        };
+
+        let is_getter_only = matches!(
+            self.0.read().properties.get(name),
+            Some(Property::Virtual { set: None, .. })
+        );
+        if is_getter_only {
+            return Ok(());
+        }
+
+        // A watcher still observes the attempted write (matching Flash),
+        // but a `ReadOnly` property is never actually updated.
+        if !self.is_property_overwritable(name) {
+            return Ok(());
+        }
+
+        let setter = match self.0.read().properties.get(name) {
+            Some(Property::Virtual { set: Some(set), .. }) => Some(*set),
+            _ => None,
+        };
+        if let Some(set) = setter {
+            set.exec(avm, context, (*self).into(), &[value])?;
+            return Ok(());
+        }
+
+        let mut data = self.0.write(context.gc_context);
+        if let Some(Property::Stored { value: slot, .. }) = data.properties.get_mut(name) {
+            *slot = value;
+        } else {
+            data.properties.insert(name, Property::Stored {
+                value,
+                attributes: Default::default(),
+                watcher: None,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn call(
+        &self,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        this: Object<'gc>,
+        args: &[Value<'gc>],
+    ) -> Result<ReturnValue<'gc>, Error> {
+        match self.0.read().function {
+            Some(function) => function.exec(avm, context, this, args),
+            None => Err("Object is not callable".into()),
+        }
+    }
+
+    fn new(
+        &self,
+        _avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        _this: Object<'gc>,
+        _args: &[Value<'gc>],
+    ) -> Result<Object<'gc>, Error> {
+        Ok(ScriptObject::object(context.gc_context, self.proto()).into())
+    }
+
+    fn delete(&self, gc_context: MutationContext<'gc, '_>, name: &str) -> bool {
+        let mut data = self.0.write(gc_context);
+        match data.properties.get(name) {
+            Some(prop) if prop.attributes().contains(Attribute::DontDelete) => false,
+            Some(_) => {
+                data.properties.remove(name);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn proto(&self) -> Option<Object<'gc>> {
+        self.0.read().proto
+    }
+
+    fn define_value(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        name: &str,
+        value: Value<'gc>,
+        attributes: EnumSet<Attribute>,
+    ) {
+        self.0.write(gc_context).properties.insert(
+            name,
+            Property::Stored {
+                value,
+                attributes,
+                watcher: None,
+            },
+        );
+    }
+
+    fn add_property(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        name: &str,
+        get: Executable<'gc>,
+        set: Option<Executable<'gc>>,
+        attributes: EnumSet<Attribute>,
+    ) {
+        self.0.write(gc_context).properties.insert(
+            name,
+            Property::Virtual {
+                get,
+                set,
+                attributes,
+                watcher: None,
+            },
+        );
+    }
+
+    fn set_watcher(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        name: String,
+        callback: Object<'gc>,
+        user_data: Value<'gc>,
+    ) {
+        let watcher = Watcher::new(callback, user_data);
+        let mut data = self.0.write(gc_context);
+        if let Some(prop) = data.properties.get_mut(&name) {
+            prop.set_watcher(Some(watcher));
+        } else {
+            // A watcher can be set on a property that doesn't exist yet;
+            // Flash creates a stored undefined slot to hold it.
+            data.properties.insert(
+                &name,
+                Property::Stored {
+                    value: Value::Undefined,
+                    attributes: Default::default(),
+                    watcher: Some(watcher),
+                },
+            );
+        }
+    }
+
+    fn remove_watcher(&self, gc_context: MutationContext<'gc, '_>, name: String) -> bool {
+        match self.0.write(gc_context).properties.get_mut(&name) {
+            Some(prop) if prop.watcher().is_some() => {
+                prop.set_watcher(None);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn has_property(&self, name: &str) -> bool {
+        if self.has_own_property(name) {
+            return true;
+        }
+
+        let mut proto = self.proto();
+        while let Some(proto_ob) = proto {
+            if proto_ob.has_own_property(name) {
+                return true;
+            }
+            proto = proto_ob.proto();
+        }
+
+        false
+    }
+
+    fn has_own_property(&self, name: &str) -> bool {
+        self.0.read().properties.contains_key(name)
+    }
+
+    fn is_property_overwritable(&self, name: &str) -> bool {
+        !matches!(
+            self.0.read().properties.get(name),
+            Some(prop) if prop.attributes().contains(Attribute::ReadOnly)
+        )
+    }
+
+    fn is_property_enumerable(&self, name: &str) -> bool {
+        match self.0.read().properties.get(name) {
+            Some(prop) => !prop.attributes().contains(Attribute::DontEnum),
+            None => false,
+        }
+    }
+
+    fn get_keys(&self) -> Vec<String> {
+        self.0
+            .read()
+            .properties
+            .keys_reverse_insertion_order()
+            .filter(|name| self.is_property_enumerable(name))
+            .map(String::from)
+            .collect()
+    }
+
+    fn own_property_names(&self) -> Vec<String> {
+        self.0
+            .read()
+            .properties
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
+    fn as_string(&self) -> String {
+        "[object Object]".to_string()
+    }
+
+    fn type_of(&self) -> &'static str {
+        self.0.read().type_of
+    }
+
+    fn as_script_object(&self) -> Option<ScriptObject<'gc>> {
+        Some(*self)
+    }
+
+    fn as_display_object(&self) -> Option<crate::display_object::DisplayObject<'gc>> {
+        None
+    }
+
+    fn as_executable(&self) -> Option<Executable<'gc>> {
+        self.0.read().function
+    }
+
+    fn as_ptr(&self) -> *const ObjectPtr {
+        self.0.as_ptr() as *const ObjectPtr
+    }
+
+    fn set_attributes(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        name: Option<&str>,
+        set_flags: EnumSet<Attribute>,
+        clear_flags: EnumSet<Attribute>,
+    ) {
+        let mut data = self.0.write(gc_context);
+        match name {
+            Some(name) => {
+                if let Some(prop) = data.properties.get_mut(name) {
+                    prop.set_attributes(set_flags, clear_flags);
+                }
+            }
+            None => {
+                for (_, prop) in data.properties.iter_mut() {
+                    prop.set_attributes(set_flags, clear_flags);
+                }
+            }
+        }
+    }
+
+    fn add_interface(&self, gc_context: MutationContext<'gc, '_>, constructor: Object<'gc>) {
+        self.0.write(gc_context).interfaces.push(constructor);
+    }
+
+    fn interfaces(&self) -> Vec<Object<'gc>> {
+        self.0.read().interfaces.clone()
+    }
+
+    fn get_length(&self) -> usize {
+        0
+    }
+
+    fn get_array(&self) -> Vec<Option<Value<'gc>>> {
+        Vec::new()
+    }
+
+    fn set_length(&self, _gc_context: MutationContext<'gc, '_>, _length: usize) {}
+
+    fn has_array_element(&self, _index: usize) -> bool {
+        false
+    }
+
+    fn get_array_element(&self, _index: usize) -> Value<'gc> {
+        Value::Undefined
+    }
+
+    fn set_array_element(
+        &self,
+        _index: usize,
+        _value: Value<'gc>,
+        _gc_context: MutationContext<'gc, '_>,
+    ) -> usize {
+        0
+    }
+
+    fn delete_array_element(&self, _index: usize, _gc_context: MutationContext<'gc, '_>) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::test_utils::with_avm;
+
+    #[test]
+    fn read_only_property_rejects_overwrite() {
+        with_avm(19, |avm, context, _root| {
+            let object = ScriptObject::bare_object(context.gc_context);
+            object.define_value(
+                context.gc_context,
+                "x",
+                Value::Number(1.0),
+                Attribute::ReadOnly.into(),
+            );
+
+            object
+                .set("x", Value::Number(2.0), avm, context)
+                .unwrap();
+
+            let value = object
+                .get_local("x", avm, context, object.into())
+                .unwrap()
+                .resolve(avm, context)
+                .unwrap();
+            assert_eq!(value, Value::Number(1.0));
+        });
+    }
+
+    #[test]
+    fn watcher_fires_and_can_replace_the_written_value() {
+        with_avm(19, |avm, context, _root| {
+            let object = ScriptObject::bare_object(context.gc_context);
+            object.define_value(
+                context.gc_context,
+                "x",
+                Value::Number(1.0),
+                Default::default(),
+            );
+
+            // A native callback that always returns 42, regardless of args.
+            let callback = ScriptObject::function(
+                context.gc_context,
+                Executable::Native(|_avm, _context, _this, _args| Ok(Value::Number(42.0).into())),
+                None,
+            );
+
+            object.set_watcher(
+                context.gc_context,
+                "x".to_string(),
+                callback.into(),
+                Value::Undefined,
+            );
+            object
+                .set("x", Value::Number(2.0), avm, context)
+                .unwrap();
+
+            let value = object
+                .get_local("x", avm, context, object.into())
+                .unwrap()
+                .resolve(avm, context)
+                .unwrap();
+            assert_eq!(value, Value::Number(42.0));
+        });
+    }
+
+    #[test]
+    fn watcher_cancels_write_by_returning_undefined() {
+        with_avm(19, |avm, context, _root| {
+            let object = ScriptObject::bare_object(context.gc_context);
+            object.define_value(
+                context.gc_context,
+                "x",
+                Value::Number(1.0),
+                Default::default(),
+            );
+
+            let callback = ScriptObject::function(
+                context.gc_context,
+                Executable::Native(|_avm, _context, _this, _args| Ok(Value::Undefined.into())),
+                None,
+            );
+
+            object.set_watcher(
+                context.gc_context,
+                "x".to_string(),
+                callback.into(),
+                Value::Undefined,
+            );
+            object
+                .set("x", Value::Number(2.0), avm, context)
+                .unwrap();
+
+            let value = object
+                .get_local("x", avm, context, object.into())
+                .unwrap()
+                .resolve(avm, context)
+                .unwrap();
+            assert_eq!(value, Value::Number(1.0));
+        });
+    }
+}
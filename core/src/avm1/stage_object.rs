@@ -0,0 +1,244 @@
+//! `TObject` implementation for movie clips and other display-list objects.
+
+use crate::avm1::function::Executable;
+use crate::avm1::object::{Object, ObjectPtr, TObject};
+use crate::avm1::property::Attribute;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::script_object::ScriptObject;
+use crate::avm1::{Avm1, Error, UpdateContext, Value};
+use crate::display_object::DisplayObject;
+use enumset::EnumSet;
+use gc_arena::{Collect, GcCell, MutationContext};
+
+/// The underlying data for a `StageObject`.
+#[derive(Collect, Debug)]
+#[collect(no_drop)]
+pub struct StageObjectData<'gc> {
+    /// The display node this object is tied to, returned by
+    /// `as_display_object` and used for `as_string` (its path). Property
+    /// reads and writes, including `_x`/`_y`, are not routed through it.
+    display_object: DisplayObject<'gc>,
+
+    /// Script-defined properties (and everything `ScriptObject` already
+    /// knows how to do: watchers, attributes, interfaces, ...) are
+    /// delegated to a plain backing object rather than reimplemented here.
+    base: ScriptObject<'gc>,
+}
+
+/// A `TObject` that represents a movie clip, button, text field or other
+/// display-list node exposed to AVM1.
+#[derive(Clone, Collect, Debug, Copy)]
+#[collect(no_drop)]
+pub struct StageObject<'gc>(GcCell<'gc, StageObjectData<'gc>>);
+
+impl<'gc> StageObject<'gc> {
+    pub fn for_display_object(
+        gc_context: MutationContext<'gc, '_>,
+        display_object: DisplayObject<'gc>,
+        proto: Option<Object<'gc>>,
+    ) -> Self {
+        Self(GcCell::allocate(
+            gc_context,
+            StageObjectData {
+                display_object,
+                base: ScriptObject::object(gc_context, proto),
+            },
+        ))
+    }
+}
+
+impl<'gc> TObject<'gc> for StageObject<'gc> {
+    fn get_local(
+        &self,
+        name: &str,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        this: Object<'gc>,
+    ) -> Result<ReturnValue<'gc>, Error> {
+        self.0.read().base.get_local(name, avm, context, this)
+    }
+
+    fn set(
+        &self,
+        name: &str,
+        value: Value<'gc>,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error> {
+        self.0.read().base.set(name, value, avm, context)
+    }
+
+    fn call(
+        &self,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        this: Object<'gc>,
+        args: &[Value<'gc>],
+    ) -> Result<ReturnValue<'gc>, Error> {
+        self.0.read().base.call(avm, context, this, args)
+    }
+
+    fn new(
+        &self,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        this: Object<'gc>,
+        args: &[Value<'gc>],
+    ) -> Result<Object<'gc>, Error> {
+        self.0.read().base.new(avm, context, this, args)
+    }
+
+    fn delete(&self, gc_context: MutationContext<'gc, '_>, name: &str) -> bool {
+        self.0.read().base.delete(gc_context, name)
+    }
+
+    fn proto(&self) -> Option<Object<'gc>> {
+        self.0.read().base.proto()
+    }
+
+    fn define_value(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        name: &str,
+        value: Value<'gc>,
+        attributes: EnumSet<Attribute>,
+    ) {
+        self.0
+            .read()
+            .base
+            .define_value(gc_context, name, value, attributes)
+    }
+
+    fn add_property(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        name: &str,
+        get: Executable<'gc>,
+        set: Option<Executable<'gc>>,
+        attributes: EnumSet<Attribute>,
+    ) {
+        self.0
+            .read()
+            .base
+            .add_property(gc_context, name, get, set, attributes)
+    }
+
+    fn set_watcher(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        name: String,
+        callback: Object<'gc>,
+        user_data: Value<'gc>,
+    ) {
+        self.0
+            .read()
+            .base
+            .set_watcher(gc_context, name, callback, user_data)
+    }
+
+    fn remove_watcher(&self, gc_context: MutationContext<'gc, '_>, name: String) -> bool {
+        self.0.read().base.remove_watcher(gc_context, name)
+    }
+
+    fn has_property(&self, name: &str) -> bool {
+        self.0.read().base.has_property(name)
+    }
+
+    fn has_own_property(&self, name: &str) -> bool {
+        self.0.read().base.has_own_property(name)
+    }
+
+    fn is_property_overwritable(&self, name: &str) -> bool {
+        self.0.read().base.is_property_overwritable(name)
+    }
+
+    fn is_property_enumerable(&self, name: &str) -> bool {
+        self.0.read().base.is_property_enumerable(name)
+    }
+
+    fn get_keys(&self) -> Vec<String> {
+        self.0.read().base.get_keys()
+    }
+
+    fn own_property_names(&self) -> Vec<String> {
+        self.0.read().base.own_property_names()
+    }
+
+    fn as_string(&self) -> String {
+        self.0.read().display_object.path()
+    }
+
+    fn type_of(&self) -> &'static str {
+        "movieclip"
+    }
+
+    fn as_script_object(&self) -> Option<ScriptObject<'gc>> {
+        Some(self.0.read().base)
+    }
+
+    fn as_display_object(&self) -> Option<DisplayObject<'gc>> {
+        Some(self.0.read().display_object)
+    }
+
+    fn as_executable(&self) -> Option<Executable<'gc>> {
+        None
+    }
+
+    fn as_ptr(&self) -> *const ObjectPtr {
+        self.0.as_ptr() as *const ObjectPtr
+    }
+
+    fn set_attributes(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        name: Option<&str>,
+        set_flags: EnumSet<Attribute>,
+        clear_flags: EnumSet<Attribute>,
+    ) {
+        self.0
+            .read()
+            .base
+            .set_attributes(gc_context, name, set_flags, clear_flags)
+    }
+
+    fn add_interface(&self, gc_context: MutationContext<'gc, '_>, constructor: Object<'gc>) {
+        self.0.read().base.add_interface(gc_context, constructor)
+    }
+
+    fn interfaces(&self) -> Vec<Object<'gc>> {
+        self.0.read().base.interfaces()
+    }
+
+    fn get_length(&self) -> usize {
+        self.0.read().base.get_length()
+    }
+
+    fn get_array(&self) -> Vec<Option<Value<'gc>>> {
+        self.0.read().base.get_array()
+    }
+
+    fn set_length(&self, gc_context: MutationContext<'gc, '_>, length: usize) {
+        self.0.read().base.set_length(gc_context, length)
+    }
+
+    fn has_array_element(&self, index: usize) -> bool {
+        self.0.read().base.has_array_element(index)
+    }
+
+    fn get_array_element(&self, index: usize) -> Value<'gc> {
+        self.0.read().base.get_array_element(index)
+    }
+
+    fn set_array_element(
+        &self,
+        index: usize,
+        value: Value<'gc>,
+        gc_context: MutationContext<'gc, '_>,
+    ) -> usize {
+        self.0.read().base.set_array_element(index, value, gc_context)
+    }
+
+    fn delete_array_element(&self, index: usize, gc_context: MutationContext<'gc, '_>) {
+        self.0.read().base.delete_array_element(index, gc_context)
+    }
+}